@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::convert::TryFrom;
 use std::ffi::OsStr;
 use std::fs::Permissions;
@@ -9,6 +10,7 @@ use std::os::unix::fs::PermissionsExt;
 pub enum ItemTypes {
     CREATE_FILE,
     // TRUNCATE_FILE, DEPRECATED
+    CREATE_AND_TRUNCATE_FILE,
     CREATE_DIRECTORY,
     TRUNCATE_DIRECTORY,
     CREATE_SUBVOLUME,
@@ -43,6 +45,7 @@ impl TryFrom<char> for ItemTypes {
     fn try_from(type_char: char) -> Result<Self, Self::Error> {
         match type_char {
             'f' => Ok(ItemTypes::CREATE_FILE),
+            'F' => Ok(ItemTypes::CREATE_AND_TRUNCATE_FILE),
             'd' => Ok(ItemTypes::CREATE_DIRECTORY),
             'D' => Ok(ItemTypes::TRUNCATE_DIRECTORY),
             'v' => Ok(ItemTypes::CREATE_SUBVOLUME),
@@ -68,6 +71,8 @@ impl TryFrom<char> for ItemTypes {
             'R' => Ok(ItemTypes::RECURSIVE_REMOVE_PATH),
             'z' => Ok(ItemTypes::RELABEL_PATH),
             'Z' => Ok(ItemTypes::RECURSIVE_RELABEL_PATH),
+            // legacy alias, behaves the same as 'z'
+            'm' => Ok(ItemTypes::RELABEL_PATH),
             invalid => Err(format!("Invalid item type: '{}'", invalid)),
         }
     }
@@ -105,27 +110,72 @@ pub enum Group<'a> {
     ID(u32),
 }
 
+/// Which timestamp(s) a cleanup-age entry should be checked against, as per
+/// the optional `[abcm]`-style prefix systemd accepts before the duration
+/// (`a` atime, `b` btime/creation, `c` ctime, `m` mtime).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AgeBy(u8);
+
+impl AgeBy {
+    pub const ATIME: AgeBy = AgeBy(0b0001);
+    pub const BTIME: AgeBy = AgeBy(0b0010);
+    pub const CTIME: AgeBy = AgeBy(0b0100);
+    pub const MTIME: AgeBy = AgeBy(0b1000);
+    pub const ALL: AgeBy = AgeBy(0b1111);
+
+    pub fn empty() -> Self {
+        AgeBy(0)
+    }
+
+    pub fn contains(self, other: AgeBy) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl Default for AgeBy {
+    fn default() -> Self {
+        AgeBy::ALL
+    }
+}
+
+impl std::ops::BitOr for AgeBy {
+    type Output = AgeBy;
+
+    fn bitor(self, rhs: AgeBy) -> AgeBy {
+        AgeBy(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitOrAssign for AgeBy {
+    fn bitor_assign(&mut self, rhs: AgeBy) {
+        self.0 |= rhs.0;
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct CleanupAge {
     pub age: u64,
+    pub age_by: AgeBy,
     pub keep_first_level: bool,
 }
 
 impl CleanupAge {
-    pub fn new(age: u64, keep_first_level: bool) -> Self {
-        CleanupAge { age, keep_first_level }
+    pub fn new(age: u64, age_by: AgeBy, keep_first_level: bool) -> Self {
+        CleanupAge { age, age_by, keep_first_level }
     }
 }
 
 #[derive(Debug, PartialEq)]
 pub struct Action<'a> {
     pub action_type: ItemTypes,
-    pub path: &'a OsStr,
+    // `Cow` because `%`-specifiers are expanded in a post-parse pass that
+    // replaces the borrowed, as-parsed bytes with an owned, expanded buffer.
+    pub path: Cow<'a, OsStr>,
     pub mode: Option<Mode>,
     pub user: Option<User<'a>>,
     pub group: Option<Group<'a>>,
     pub age: Option<CleanupAge>,
-    pub argument: Option<&'a OsStr>,
+    pub argument: Option<Cow<'a, OsStr>>,
     pub boot_only: bool,
     pub append_or_force: bool,
     pub allow_failure: bool,
@@ -135,7 +185,7 @@ impl <'a> Default for Action<'a> {
     fn default() -> Self {
         Action {
             action_type: ItemTypes::CREATE_DIRECTORY,
-            path: OsStr::new(""),
+            path: Cow::Borrowed(OsStr::new("")),
             mode: None,
             user: None,
             group: None,
@@ -0,0 +1,369 @@
+use std::borrow::Cow;
+use std::env;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+
+use crate::common::action::Action;
+
+extern "C" {
+    fn getuid() -> u32;
+    fn getgid() -> u32;
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SpecifierError {
+    UnknownSpecifier(char),
+    TrailingPercent,
+}
+
+/// Source values for the systemd-style `%`-specifiers that may appear in
+/// the `path` and `argument` fields of an [`Action`]. Each value that is not
+/// pre-seeded is looked up from the running system every time it is needed
+/// (lookups are not cached), but a caller (typically a test) can pre-seed
+/// any of them with `with_*` so expansion is deterministic.
+#[derive(Debug, Default)]
+pub struct Specifiers {
+    home: Option<OsString>,
+    host_name: Option<OsString>,
+    machine_id: Option<OsString>,
+    boot_id: Option<OsString>,
+    kernel_release: Option<OsString>,
+    user_name: Option<OsString>,
+    user_id: Option<u32>,
+    group_name: Option<OsString>,
+    group_id: Option<u32>,
+    runtime_dir: Option<OsString>,
+    tmp_dir: Option<OsString>,
+    persistent_tmp_dir: Option<OsString>,
+}
+
+impl Specifiers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_home(mut self, home: impl Into<OsString>) -> Self {
+        self.home = Some(home.into());
+        self
+    }
+
+    pub fn with_host_name(mut self, host_name: impl Into<OsString>) -> Self {
+        self.host_name = Some(host_name.into());
+        self
+    }
+
+    pub fn with_machine_id(mut self, machine_id: impl Into<OsString>) -> Self {
+        self.machine_id = Some(machine_id.into());
+        self
+    }
+
+    pub fn with_boot_id(mut self, boot_id: impl Into<OsString>) -> Self {
+        self.boot_id = Some(boot_id.into());
+        self
+    }
+
+    pub fn with_kernel_release(mut self, kernel_release: impl Into<OsString>) -> Self {
+        self.kernel_release = Some(kernel_release.into());
+        self
+    }
+
+    pub fn with_user(mut self, name: impl Into<OsString>, id: u32) -> Self {
+        self.user_name = Some(name.into());
+        self.user_id = Some(id);
+        self
+    }
+
+    pub fn with_group(mut self, name: impl Into<OsString>, id: u32) -> Self {
+        self.group_name = Some(name.into());
+        self.group_id = Some(id);
+        self
+    }
+
+    pub fn with_runtime_dir(mut self, runtime_dir: impl Into<OsString>) -> Self {
+        self.runtime_dir = Some(runtime_dir.into());
+        self
+    }
+
+    pub fn with_tmp_dir(mut self, tmp_dir: impl Into<OsString>) -> Self {
+        self.tmp_dir = Some(tmp_dir.into());
+        self
+    }
+
+    pub fn with_persistent_tmp_dir(mut self, persistent_tmp_dir: impl Into<OsString>) -> Self {
+        self.persistent_tmp_dir = Some(persistent_tmp_dir.into());
+        self
+    }
+
+    fn home(&self) -> Cow<OsStr> {
+        match &self.home {
+            Some(home) => Cow::Borrowed(home.as_os_str()),
+            None => Cow::Owned(env::var_os("HOME").unwrap_or_default()),
+        }
+    }
+
+    fn host_name(&self) -> Cow<OsStr> {
+        match &self.host_name {
+            Some(host_name) => Cow::Borrowed(host_name.as_os_str()),
+            None => Cow::Owned(read_trimmed_os_string("/proc/sys/kernel/hostname")),
+        }
+    }
+
+    fn machine_id(&self) -> Cow<OsStr> {
+        match &self.machine_id {
+            Some(machine_id) => Cow::Borrowed(machine_id.as_os_str()),
+            None => Cow::Owned(read_trimmed_os_string("/etc/machine-id")),
+        }
+    }
+
+    fn boot_id(&self) -> Cow<OsStr> {
+        match &self.boot_id {
+            Some(boot_id) => Cow::Borrowed(boot_id.as_os_str()),
+            None => Cow::Owned(read_trimmed_os_string("/proc/sys/kernel/random/boot_id")),
+        }
+    }
+
+    fn kernel_release(&self) -> Cow<OsStr> {
+        match &self.kernel_release {
+            Some(kernel_release) => Cow::Borrowed(kernel_release.as_os_str()),
+            None => Cow::Owned(read_trimmed_os_string("/proc/sys/kernel/osrelease")),
+        }
+    }
+
+    fn user_name(&self) -> Cow<OsStr> {
+        match &self.user_name {
+            Some(user_name) => Cow::Borrowed(user_name.as_os_str()),
+            None => Cow::Owned(env::var_os("USER").unwrap_or_default()),
+        }
+    }
+
+    fn user_id(&self) -> u32 {
+        self.user_id.unwrap_or_else(|| unsafe { getuid() })
+    }
+
+    fn group_name(&self) -> Cow<OsStr> {
+        match &self.group_name {
+            Some(group_name) => Cow::Borrowed(group_name.as_os_str()),
+            // Unlike $HOME/$USER, $GROUP isn't set by login shells on most
+            // systems, so this falls back to an empty string unless the
+            // caller pre-seeds it with `with_group`.
+            None => Cow::Owned(env::var_os("GROUP").unwrap_or_default()),
+        }
+    }
+
+    fn group_id(&self) -> u32 {
+        self.group_id.unwrap_or_else(|| unsafe { getgid() })
+    }
+
+    fn runtime_dir(&self) -> Cow<OsStr> {
+        match &self.runtime_dir {
+            Some(runtime_dir) => Cow::Borrowed(runtime_dir.as_os_str()),
+            None => Cow::Owned(env::var_os("XDG_RUNTIME_DIR").unwrap_or_else(|| "/run".into())),
+        }
+    }
+
+    fn tmp_dir(&self) -> Cow<OsStr> {
+        match &self.tmp_dir {
+            Some(tmp_dir) => Cow::Borrowed(tmp_dir.as_os_str()),
+            None => Cow::Owned(env::var_os("TMPDIR").unwrap_or_else(|| "/tmp".into())),
+        }
+    }
+
+    fn persistent_tmp_dir(&self) -> Cow<OsStr> {
+        match &self.persistent_tmp_dir {
+            Some(persistent_tmp_dir) => Cow::Borrowed(persistent_tmp_dir.as_os_str()),
+            None => Cow::Owned(env::var_os("VARTMPDIR").unwrap_or_else(|| "/var/tmp".into())),
+        }
+    }
+}
+
+fn read_trimmed_os_string(path: &str) -> OsString {
+    let contents = fs::read(path).unwrap_or_default();
+    let trimmed = contents
+        .iter()
+        .rposition(|b| !b.is_ascii_whitespace())
+        .map(|end| &contents[..=end])
+        .unwrap_or(&[]);
+    OsString::from_vec(trimmed.to_vec())
+}
+
+/// Expand the systemd-style `%`-specifiers found in `input`, substituting
+/// the values held by `specifiers`. `%%` is an escaped, literal `%`; any
+/// other `%` must be followed by one of the known specifier characters, and
+/// a `%` as the last byte of `input` is a parse error.
+pub fn expand(input: &OsStr, specifiers: &Specifiers) -> Result<OsString, SpecifierError> {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut iter = bytes.iter().copied();
+
+    while let Some(byte) = iter.next() {
+        if byte != b'%' {
+            out.push(byte);
+            continue;
+        }
+
+        match iter.next() {
+            None => return Err(SpecifierError::TrailingPercent),
+            Some(b'%') => out.push(b'%'),
+            Some(b'h') => out.extend_from_slice(specifiers.home().as_bytes()),
+            Some(b'H') => out.extend_from_slice(specifiers.host_name().as_bytes()),
+            Some(b'm') => out.extend_from_slice(specifiers.machine_id().as_bytes()),
+            Some(b'b') => out.extend_from_slice(specifiers.boot_id().as_bytes()),
+            Some(b'v') => out.extend_from_slice(specifiers.kernel_release().as_bytes()),
+            Some(b't') => out.extend_from_slice(specifiers.runtime_dir().as_bytes()),
+            Some(b'u') => out.extend_from_slice(specifiers.user_name().as_bytes()),
+            Some(b'U') => out.extend_from_slice(specifiers.user_id().to_string().as_bytes()),
+            Some(b'g') => out.extend_from_slice(specifiers.group_name().as_bytes()),
+            Some(b'G') => out.extend_from_slice(specifiers.group_id().to_string().as_bytes()),
+            Some(b'T') => out.extend_from_slice(specifiers.tmp_dir().as_bytes()),
+            Some(b'V') => out.extend_from_slice(specifiers.persistent_tmp_dir().as_bytes()),
+            Some(other) => return Err(SpecifierError::UnknownSpecifier(other as char)),
+        }
+    }
+
+    Ok(OsString::from_vec(out))
+}
+
+/// Run [`expand`] over the `path` and `argument` fields of `action`,
+/// replacing their borrowed, as-parsed bytes with the owned, expanded
+/// result.
+pub fn expand_action<'a>(
+    action: Action<'a>,
+    specifiers: &Specifiers,
+) -> Result<Action<'a>, SpecifierError> {
+    let path = Cow::Owned(expand(&action.path, specifiers)?);
+    let argument = action
+        .argument
+        .map(|argument| expand(&argument, specifiers))
+        .transpose()?
+        .map(Cow::Owned);
+
+    Ok(Action {
+        path,
+        argument,
+        ..action
+    })
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn test_expand_literal_percent() {
+        let specifiers = Specifiers::new();
+        assert_eq!(
+            OsString::from("100%"),
+            expand(OsStr::new("100%%"), &specifiers).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_expand_known_specifiers() {
+        let specifiers = Specifiers::new()
+            .with_home("/home/alice")
+            .with_user("alice", 1000);
+
+        assert_eq!(
+            OsString::from("/home/alice/cache"),
+            expand(OsStr::new("%h/cache"), &specifiers).unwrap()
+        );
+        assert_eq!(
+            OsString::from("alice-1000"),
+            expand(OsStr::new("%u-%U"), &specifiers).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_expand_runtime_and_tmp_dir_specifiers() {
+        let specifiers = Specifiers::new()
+            .with_runtime_dir("/run/user/1000")
+            .with_tmp_dir("/tmp")
+            .with_persistent_tmp_dir("/var/tmp");
+
+        assert_eq!(
+            OsString::from("/run/user/1000/foo"),
+            expand(OsStr::new("%t/foo"), &specifiers).unwrap()
+        );
+        assert_eq!(
+            OsString::from("/tmp/foo"),
+            expand(OsStr::new("%T/foo"), &specifiers).unwrap()
+        );
+        assert_eq!(
+            OsString::from("/var/tmp/foo"),
+            expand(OsStr::new("%V/foo"), &specifiers).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_expand_host_and_machine_specifiers() {
+        let specifiers = Specifiers::new()
+            .with_host_name("testhost")
+            .with_machine_id("0123456789abcdef0123456789abcdef")
+            .with_boot_id("fedcba9876543210fedcba9876543210")
+            .with_kernel_release("6.1.0");
+
+        assert_eq!(
+            OsString::from("testhost"),
+            expand(OsStr::new("%H"), &specifiers).unwrap()
+        );
+        assert_eq!(
+            OsString::from("0123456789abcdef0123456789abcdef"),
+            expand(OsStr::new("%m"), &specifiers).unwrap()
+        );
+        assert_eq!(
+            OsString::from("fedcba9876543210fedcba9876543210"),
+            expand(OsStr::new("%b"), &specifiers).unwrap()
+        );
+        assert_eq!(
+            OsString::from("6.1.0"),
+            expand(OsStr::new("%v"), &specifiers).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_expand_group_specifiers() {
+        let specifiers = Specifiers::new().with_group("wheel", 10);
+
+        assert_eq!(
+            OsString::from("wheel-10"),
+            expand(OsStr::new("%g-%G"), &specifiers).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_expand_unknown_specifier() {
+        let specifiers = Specifiers::new();
+        assert_eq!(
+            Err(SpecifierError::UnknownSpecifier('Q')),
+            expand(OsStr::new("%Q"), &specifiers)
+        );
+    }
+
+    #[test]
+    fn test_expand_trailing_percent() {
+        let specifiers = Specifiers::new();
+        assert_eq!(
+            Err(SpecifierError::TrailingPercent),
+            expand(OsStr::new("/var/tmp/%"), &specifiers)
+        );
+    }
+
+    #[test]
+    fn test_expand_action() {
+        let specifiers = Specifiers::new().with_home("/home/alice");
+        let action = Action {
+            path: Cow::Borrowed(OsStr::new("%h/.cache")),
+            argument: Some(Cow::Borrowed(OsStr::new("%h/.cache/origin"))),
+            ..Action::default()
+        };
+
+        let expanded = expand_action(action, &specifiers).unwrap();
+        assert_eq!(OsStr::new("/home/alice/.cache"), &*expanded.path);
+        assert_eq!(
+            Some(OsStr::new("/home/alice/.cache/origin")),
+            expanded.argument.as_deref()
+        );
+    }
+}
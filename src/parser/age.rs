@@ -2,13 +2,15 @@ use btoi::btoi;
 
 use nom::branch::alt;
 use nom::bytes::complete::tag;
-use nom::character::complete::{char, digit1, space1};
-use nom::combinator::{opt, peek};
+use nom::character::complete::{char, digit1, one_of, space1};
+use nom::combinator::{cut, map, opt, peek};
+use nom::error::{Error, ErrorKind};
+use nom::multi::many1;
 use nom::sequence::terminated;
-use nom::IResult;
+use nom::{Err, IResult};
 
 
-use crate::common::action::CleanupAge;
+use crate::common::action::{AgeBy, CleanupAge};
 use super::basic::empty_placeholder;
 
 // Time unit multipliers
@@ -107,6 +109,40 @@ fn age_without_unit(input: &[u8]) -> IResult<&[u8], u64> {
     Ok((input, btoi::<u64>(digits).unwrap() * USEC_PER_SEC))
 }
 
+fn age_by_flag(input: &[u8]) -> IResult<&[u8], AgeBy> {
+    map(one_of("abcm"), |flag| match flag {
+        'a' => AgeBy::ATIME,
+        'b' => AgeBy::BTIME,
+        'c' => AgeBy::CTIME,
+        'm' => AgeBy::MTIME,
+        _ => unreachable!(),
+    })(input)
+}
+
+// A bracketed group of flags, e.g. `[ab]`. Once the opening `[` is seen we
+// commit to this being a group: an empty `[]`, an unknown flag character, or
+// a missing closing `]` is a hard `Failure` rather than a recoverable
+// `Error`, so `opt` in `age_by` can't silently treat it as "group absent"
+// and skip over it. Duplicated flags are rejected explicitly below.
+fn age_by_group(input: &[u8]) -> IResult<&[u8], AgeBy> {
+    let (input, _) = char('[')(input)?;
+    let (input, flags) = cut(terminated(many1(age_by_flag), char(']')))(input)?;
+
+    let mut age_by = AgeBy::empty();
+    for flag in flags {
+        if age_by.contains(flag) {
+            return Err(Err::Failure(Error::new(input, ErrorKind::Verify)));
+        }
+        age_by |= flag;
+    }
+
+    Ok((input, age_by))
+}
+
+fn age_by(input: &[u8]) -> IResult<&[u8], AgeBy> {
+    map(opt(age_by_group), |age_by| age_by.unwrap_or_default())(input)
+}
+
 pub fn age(input: &[u8]) -> IResult<&[u8], Option<CleanupAge>> {
     let (input, keep_first_level) = opt(char('~'))(input)?;
     let (input, omitted) = opt(empty_placeholder)(input)?;
@@ -114,6 +150,7 @@ pub fn age(input: &[u8]) -> IResult<&[u8], Option<CleanupAge>> {
         return Ok((input, None));
     }
 
+    let (input, age_by) = age_by(input)?;
     let (input, age) = alt((
         // If an integer is given without a unit, s is assumed.
         age_without_unit,
@@ -121,7 +158,10 @@ pub fn age(input: &[u8]) -> IResult<&[u8], Option<CleanupAge>> {
         age_with_unit,
     ))(input)?;
 
-    Ok((input, Some(CleanupAge::new(age, keep_first_level.is_some()))))
+    Ok((
+        input,
+        Some(CleanupAge::new(age, age_by, keep_first_level.is_some())),
+    ))
 }
 
 
@@ -136,23 +176,68 @@ mod test {
         assert_eq!(None, age(b"-").unwrap().1,);
 
         assert_eq!(
-            Some(CleanupAge::new(5_000_000, false)),
+            Some(CleanupAge::new(5_000_000, AgeBy::default(), false)),
             age(b"5s").unwrap().1,
         );
 
         assert_eq!(
-            Some(CleanupAge::new(60_000_000, false)),
+            Some(CleanupAge::new(60_000_000, AgeBy::default(), false)),
             age(b"1m").unwrap().1,
         );
 
         assert_eq!(
-            Some(CleanupAge::new(110_000_000, false)),
+            Some(CleanupAge::new(110_000_000, AgeBy::default(), false)),
             age(b"1m50s").unwrap().1,
         );
 
         assert_eq!(
-            Some(CleanupAge::new(60_000_000, false)),
+            Some(CleanupAge::new(60_000_000, AgeBy::default(), false)),
             age(b"60 ").unwrap().1,
         );
     }
+
+    #[test]
+    fn test_age_by() {
+        assert_eq!(AgeBy::default(), age_by(b"").unwrap().1);
+
+        assert_eq!(AgeBy::ATIME | AgeBy::BTIME, age_by(b"[ab]").unwrap().1);
+
+        assert_eq!(
+            AgeBy::ATIME | AgeBy::BTIME | AgeBy::CTIME | AgeBy::MTIME,
+            age_by(b"[abcm]").unwrap().1
+        );
+    }
+
+    #[test]
+    fn test_age_by_rejects_empty_duplicate_and_unknown_flags() {
+        assert!(age_by_group(b"[]").is_err());
+        assert!(age_by_group(b"[aa]").is_err());
+        assert!(age_by_group(b"[x]").is_err());
+    }
+
+    #[test]
+    fn test_age_rejects_unknown_age_by_flag() {
+        assert!(age(b"[x]1d").is_err());
+    }
+
+    #[test]
+    fn test_age_with_age_by_prefix() {
+        assert_eq!(
+            Some(CleanupAge::new(
+                97_200_000_000,
+                AgeBy::ATIME | AgeBy::BTIME,
+                false
+            )),
+            age(b"[ab]1d3h").unwrap().1,
+        );
+
+        assert_eq!(
+            Some(CleanupAge::new(
+                97_200_000_000,
+                AgeBy::ATIME | AgeBy::BTIME,
+                true
+            )),
+            age(b"~[ab]1d3h").unwrap().1,
+        );
+    }
 }
\ No newline at end of file
@@ -0,0 +1,37 @@
+/// Unifies the byte- and string-shaped inputs `parse_line` accepts behind a
+/// single trait, so callers can hand it a `&str` line read from a
+/// config file or a `Vec<u8>` read from disk without manual `.as_bytes()`
+/// juggling.
+pub trait BytesContainer {
+    fn as_parse_bytes(&self) -> &[u8];
+}
+
+impl BytesContainer for [u8] {
+    fn as_parse_bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+impl<const N: usize> BytesContainer for [u8; N] {
+    fn as_parse_bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+impl BytesContainer for str {
+    fn as_parse_bytes(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl BytesContainer for String {
+    fn as_parse_bytes(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl BytesContainer for Vec<u8> {
+    fn as_parse_bytes(&self) -> &[u8] {
+        self
+    }
+}
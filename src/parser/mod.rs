@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::convert::TryFrom;
 use std::ffi::OsStr;
 use std::os::unix::ffi::OsStrExt;
@@ -16,8 +17,10 @@ use crate::common::action::{ItemTypes, Mode, User, Group, Action};
 
 mod basic;
 mod age;
+mod bytes_container;
 use basic::empty_placeholder;
 use age::age;
+pub use bytes_container::BytesContainer;
 
 
 // NOTE: taken from systemd source code
@@ -125,7 +128,7 @@ fn argument(input: &[u8]) -> IResult<&[u8], Option<&OsStr>> {
     ))(input)
 }
 
-pub fn parse_line(input: &[u8]) -> IResult<&[u8], Action> {
+fn parse_line_bytes(input: &[u8]) -> IResult<&[u8], Action> {
     let (input, (action_type, boot_only, append_or_force, allow_failure)) = item_type(input)?;
     let (input, path_os_str) = path(input)?;
     let (input, _) = space1(input)?;
@@ -143,12 +146,12 @@ pub fn parse_line(input: &[u8]) -> IResult<&[u8], Action> {
         input,
         Action {
             action_type,
-            path: path_os_str,
+            path: Cow::Borrowed(path_os_str),
             mode,
             user,
             group,
             age,
-            argument,
+            argument: argument.map(Cow::Borrowed),
             boot_only,
             append_or_force,
             allow_failure,
@@ -156,11 +159,15 @@ pub fn parse_line(input: &[u8]) -> IResult<&[u8], Action> {
     ))
 }
 
+pub fn parse_line<T: BytesContainer + ?Sized>(input: &T) -> IResult<&[u8], Action> {
+    parse_line_bytes(input.as_parse_bytes())
+}
+
 #[cfg(test)]
 mod test {
 
     use super::*;
-    use crate::common::action::CleanupAge;
+    use crate::common::action::{AgeBy, CleanupAge};
 
     #[test]
     fn test_parse_item_type() {
@@ -183,6 +190,16 @@ mod test {
             (ItemTypes::RELABEL_PATH, false, false, true),
             item_type(b"z- ").unwrap().1
         );
+
+        assert_eq!(
+            (ItemTypes::CREATE_AND_TRUNCATE_FILE, false, false, false),
+            item_type(b"F ").unwrap().1
+        );
+
+        assert_eq!(
+            (ItemTypes::RELABEL_PATH, false, false, false),
+            item_type(b"m ").unwrap().1
+        );
     }
 
     #[test]
@@ -262,7 +279,7 @@ mod test {
         assert_eq!(
             Action {
                 action_type: ItemTypes::RELABEL_PATH,
-                path: &OsStr::new("/tmp/z/f"),
+                path: Cow::Borrowed(OsStr::new("/tmp/z/f")),
                 mode: Some(Mode::new(false, 0o755)),
                 user: Some(User::Name(OsStr::new("daemon"))),
                 group: Some(Group::Name(OsStr::new("daemon"))),
@@ -276,7 +293,7 @@ mod test {
         assert_eq!(
             Action {
                 action_type: ItemTypes::CREATE_FILE,
-                path: &OsStr::new("/tmp/z/f"),
+                path: Cow::Borrowed(OsStr::new("/tmp/z/f")),
                 mode: Some(Mode::new(false, 0o755)),
                 user: Some(User::Name(OsStr::new("daemon"))),
                 group: Some(Group::Name(OsStr::new("daemon"))),
@@ -290,12 +307,13 @@ mod test {
         assert_eq!(
             Action {
                 action_type: ItemTypes::CREATE_DIRECTORY,
-                path: &OsStr::new("/tmp/z/f"),
+                path: Cow::Borrowed(OsStr::new("/tmp/z/f")),
                 mode: Some(Mode::new(false, 0o755)),
                 user: Some(User::Name(OsStr::new("daemon"))),
                 group: Some(Group::Name(OsStr::new("daemon"))),
                 age: Some(CleanupAge {
                     age: 97_200_000_000,
+                    age_by: AgeBy::default(),
                     keep_first_level: false
                 }),
                 .. Action::default()
@@ -308,11 +326,11 @@ mod test {
         assert_eq!(
             Action {
                 action_type: ItemTypes::CREATE_DIRECTORY,
-                path: &OsStr::new("/tmp/z/f"),
+                path: Cow::Borrowed(OsStr::new("/tmp/z/f")),
                 mode: Some(Mode::new(false, 0o755)),
                 user: Some(User::Name(OsStr::new("daemon"))),
                 group: Some(Group::Name(OsStr::new("daemon"))),
-                argument: Some(OsStr::new("/tmp/C/1-origin")),
+                argument: Some(Cow::Borrowed(OsStr::new("/tmp/C/1-origin"))),
                 .. Action::default()
             },
             parse_line(b"d  /tmp/z/f    0755 daemon daemon - /tmp/C/1-origin")
@@ -320,4 +338,56 @@ mod test {
                 .1
         );
     }
+
+    #[test]
+    fn test_parse_line_f_and_m_item_types() {
+        assert_eq!(
+            Action {
+                action_type: ItemTypes::CREATE_AND_TRUNCATE_FILE,
+                path: Cow::Borrowed(OsStr::new("/tmp/z/f")),
+                mode: Some(Mode::new(false, 0o755)),
+                user: Some(User::Name(OsStr::new("daemon"))),
+                group: Some(Group::Name(OsStr::new("daemon"))),
+                .. Action::default()
+            },
+            parse_line(b"F     /tmp/z/f    0755 daemon daemon - -")
+                .unwrap()
+                .1
+        );
+
+        assert_eq!(
+            Action {
+                action_type: ItemTypes::RELABEL_PATH,
+                path: Cow::Borrowed(OsStr::new("/tmp/z/f")),
+                mode: Some(Mode::new(false, 0o755)),
+                user: Some(User::Name(OsStr::new("daemon"))),
+                group: Some(Group::Name(OsStr::new("daemon"))),
+                .. Action::default()
+            },
+            parse_line(b"m     /tmp/z/f    0755 daemon daemon - -")
+                .unwrap()
+                .1
+        );
+    }
+
+    #[test]
+    fn test_parse_line_bytes_container_inputs() {
+        let expected = Action {
+            action_type: ItemTypes::CREATE_DIRECTORY,
+            path: Cow::Borrowed(OsStr::new("/tmp/z/f")),
+            mode: Some(Mode::new(false, 0o755)),
+            user: Some(User::Name(OsStr::new("daemon"))),
+            group: Some(Group::Name(OsStr::new("daemon"))),
+            .. Action::default()
+        };
+
+        let line = "d     /tmp/z/f    0755 daemon daemon - -";
+        assert_eq!(expected, parse_line(line).unwrap().1);
+
+        let line = line.to_string();
+        assert_eq!(expected, parse_line(&line).unwrap().1);
+
+        let line = line.into_bytes();
+        assert_eq!(expected, parse_line(&line).unwrap().1);
+    }
 }